@@ -0,0 +1,14 @@
+/// Configuration for the [`Driver`] and the tasks it spawns.
+///
+/// [`Driver`]: super::Driver
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Whether to recover lost frames using Opus in-band FEC data carried in the *following*
+    /// packet.
+    ///
+    /// Each SSRC's packets are held back one tick (20ms) before decoding whether or not this is
+    /// set. When `false` (the default), losses are concealed using Opus's packet-loss
+    /// concealment (PLC) alone; the one-tick delay still applies.
+    pub decode_fec: bool,
+}
@@ -0,0 +1,176 @@
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use cpal::{
+    traits::{DeviceTrait, StreamTrait},
+    BuildStreamError, Device, PlayStreamError, SampleFormat, Stream, StreamConfig,
+};
+
+use crate::events::context::data::VoiceTick;
+
+/// Receives each [`VoiceTick`], mixes all currently-speaking SSRCs down to a single stereo
+/// buffer, and renders the result to a local cpal output [`Device`] so a call can be monitored
+/// on local speakers.
+///
+/// This is intended to be driven from a [`VoiceTickEvent`] handler: call [`Self::mix_and_play`]
+/// with each tick as it arrives.
+///
+/// [`VoiceTickEvent`]: crate::EventContext::VoiceTick
+pub struct CpalPlayback {
+    buf: Arc<Mutex<Vec<i16>>>,
+    failed: Arc<AtomicBool>,
+    _stream: Stream,
+}
+
+impl CpalPlayback {
+    /// Opens `device` for playback using its default output configuration.
+    pub fn open(device: &Device) -> Result<Self, CpalSinkError> {
+        let config = device
+            .default_output_config()
+            .map_err(CpalSinkError::Config)?;
+
+        Self::open_with_config(device, &config.config(), config.sample_format())
+    }
+
+    /// Opens `device` for playback using a caller-chosen output configuration.
+    pub fn open_with_config(
+        device: &Device,
+        stream_config: &StreamConfig,
+        sample_format: SampleFormat,
+    ) -> Result<Self, CpalSinkError> {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let failed = Arc::new(AtomicBool::new(false));
+        let out_channels = stream_config.channels as usize;
+
+        let read_buf = buf.clone();
+        let err_cb = {
+            let failed = failed.clone();
+            move |err: cpal::StreamError| {
+                tracing::error!("cpal output stream error: {err}");
+                failed.store(true, Ordering::SeqCst);
+            }
+        };
+
+        let stream = match sample_format {
+            SampleFormat::F32 => device
+                .build_output_stream(
+                    stream_config,
+                    move |out: &mut [f32], _: &_| fill(out, out_channels, &read_buf),
+                    err_cb,
+                    None,
+                )
+                .map_err(CpalSinkError::Build)?,
+            _ => return Err(CpalSinkError::UnsupportedSampleFormat),
+        };
+
+        stream.play().map_err(CpalSinkError::Play)?;
+
+        Ok(Self {
+            buf,
+            failed,
+            _stream: stream,
+        })
+    }
+
+    /// Mixes every speaking user's [`VoiceData::decoded_voice`] in `tick` down to stereo, and
+    /// queues the result for playback.
+    ///
+    /// Users without decoded audio this tick (silent, or not configured to decode) are treated
+    /// as silence rather than skipped, so mixed output stays time-aligned with the call.
+    ///
+    /// [`VoiceData::decoded_voice`]: crate::events::context::data::VoiceData::decoded_voice
+    pub fn mix_and_play(&self, tick: &VoiceTick) -> Result<(), CpalSinkError> {
+        if self.failed.load(Ordering::SeqCst) {
+            return Err(CpalSinkError::StreamFailed);
+        }
+
+        // Start from one full 20ms tick of 48kHz stereo silence, so a tick in which nobody has
+        // decoded audio still queues its share of output.
+        let mut mixed: Vec<i32> = vec![0; 960 * 2];
+
+        for data in tick.speaking.values() {
+            let Some(samples) = &data.decoded_voice else {
+                continue;
+            };
+
+            if mixed.len() < samples.len() {
+                mixed.resize(samples.len(), 0);
+            }
+
+            for (acc, &sample) in mixed.iter_mut().zip(samples.iter()) {
+                *acc += sample as i32;
+            }
+        }
+
+        let mut buf = self.buf.lock().expect("cpal playback buffer poisoned");
+        buf.extend(mixed.into_iter().map(|s| s.clamp(i16::MIN as i32, i16::MAX as i32) as i16));
+
+        Ok(())
+    }
+}
+
+/// Pulls interleaved i16 samples out of the shared mix buffer and writes them as `f32` into
+/// cpal's output callback, padding with silence if the mixer hasn't kept up.
+fn fill(out: &mut [f32], out_channels: usize, buf: &Arc<Mutex<Vec<i16>>>) {
+    let mut buf = buf.lock().expect("cpal playback buffer poisoned");
+    let needed = out.len() / out_channels.max(1) * 2;
+    let have = needed.min(buf.len());
+
+    let mut drained = buf.drain(..have);
+    for frame in out.chunks_mut(out_channels) {
+        let l_sample = drained.next().unwrap_or(0);
+        let r_sample = drained.next().unwrap_or(l_sample);
+        let l = l_sample as f32 / i16::MAX as f32;
+        let r = r_sample as f32 / i16::MAX as f32;
+
+        for (ch, sample) in frame.iter_mut().enumerate() {
+            *sample = if ch % 2 == 0 { l } else { r };
+        }
+    }
+}
+
+/// Errors encountered while opening or running a [`CpalPlayback`] sink.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum CpalSinkError {
+    /// Failed to query the device's default output configuration.
+    Config(cpal::DefaultStreamConfigError),
+    /// Failed to build the output stream.
+    Build(BuildStreamError),
+    /// Failed to start the output stream playing.
+    Play(PlayStreamError),
+    /// The device only offered a sample format this sink doesn't support.
+    UnsupportedSampleFormat,
+    /// The output stream has already failed; see the logged `cpal::StreamError` for why.
+    StreamFailed,
+}
+
+impl Display for CpalSinkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cpal playback sink error: ")?;
+        match self {
+            Self::Config(e) => write!(f, "{e}"),
+            Self::Build(e) => write!(f, "{e}"),
+            Self::Play(e) => write!(f, "{e}"),
+            Self::UnsupportedSampleFormat => f.write_str("unsupported sample format"),
+            Self::StreamFailed => f.write_str("stream already failed"),
+        }
+    }
+}
+
+impl StdError for CpalSinkError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Config(e) => Some(e),
+            Self::Build(e) => Some(e),
+            Self::Play(e) => Some(e),
+            Self::UnsupportedSampleFormat | Self::StreamFailed => None,
+        }
+    }
+}
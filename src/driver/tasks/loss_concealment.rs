@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use audiopus::{coder::Decoder as OpusDecoder, Error as OpusError};
+
+use crate::driver::Config;
+
+/// Number of 48kHz samples per channel in a single 20ms tick.
+const MONO_FRAME_SIZE: usize = 960;
+const STEREO_FRAME_SIZE: usize = MONO_FRAME_SIZE * 2;
+
+/// Per-SSRC state needed to run Opus packet-loss concealment (PLC) and in-band FEC recovery.
+///
+/// Songbird clocks out audio for a given tick one packet behind the network, holding the most
+/// recently received packet in [`Self::held`] until the following tick. This gives the decoder a
+/// chance to pull a lost frame's audio out of the *next* packet's FEC payload, at the cost of an
+/// extra tick (20ms) of latency. When FEC is disabled, or the next packet has no usable FEC data,
+/// the decoder instead synthesises concealment audio via Opus's own PLC.
+pub struct SsrcLossState {
+    held: Option<(u16, Vec<u8>)>,
+    /// Sequence number of the last packet actually decoded as `due` (genuinely or via
+    /// concealment/recovery). `None` until the first packet has made it through the one-tick
+    /// delay buffer, which distinguishes "nothing is due yet because we just started" from "a
+    /// packet was due and got lost".
+    last_due_seq: Option<u16>,
+    enable_fec: bool,
+}
+
+impl SsrcLossState {
+    /// Creates a fresh loss-concealment tracker for one SSRC.
+    ///
+    /// `enable_fec` mirrors [`Config::decode_fec`]: when set, a lost frame is recovered from the
+    /// following packet's FEC data where possible, rather than merely concealed with PLC.
+    pub fn new(enable_fec: bool) -> Self {
+        Self {
+            held: None,
+            last_due_seq: None,
+            enable_fec,
+        }
+    }
+
+    /// Decodes the next tick of audio for this SSRC, concealing or recovering losses as needed.
+    ///
+    /// `seq`/`payload` describe the packet received (if any) for the *current* network tick; due
+    /// to the one-tick delay buffer, the audio actually produced here belongs to the *previous*
+    /// tick's packet. Returns the decoded samples (interleaved 16-bit stereo) alongside whether
+    /// they were concealed (PLC or FEC recovery) rather than decoded from a genuine, on-time
+    /// packet; the caller is responsible for pairing this with whatever [`RtpData`] belongs to
+    /// the tick being clocked out.
+    ///
+    /// [`RtpData`]: crate::events::context::data::RtpData
+    pub fn decode_tick(
+        &mut self,
+        decoder: &mut OpusDecoder,
+        seq: Option<u16>,
+        payload: Option<Vec<u8>>,
+    ) -> Result<(Vec<i16>, bool), OpusError> {
+        // The packet due to be decoded this tick is whatever was held back last call; the
+        // packet passed in now becomes the lookahead used to FEC-recover it if it's missing.
+        let due = self.held.take();
+        self.held = seq.zip(payload);
+
+        if let Some((due_seq, _)) = &due {
+            if let Some(expected) = self.last_due_seq.map(|s| s.wrapping_add(1)) {
+                if *due_seq != expected {
+                    // More than one packet's worth of gap opened up since the last due packet;
+                    // our one-tick buffer can only recover/conceal a single frame per tick, so
+                    // the remainder is simply missed. Surface it for diagnostics.
+                    tracing::debug!(
+                        "lost {} packet(s) before seq {due_seq} (expected {expected})",
+                        due_seq.wrapping_sub(expected),
+                    );
+                }
+            }
+        }
+
+        let mut out = vec![0i16; STEREO_FRAME_SIZE];
+
+        let concealed = match due {
+            Some((due_seq, data)) => {
+                let len = decoder.decode(Some(data.as_slice()), &mut out, false)?;
+                out.truncate(len * 2);
+                self.last_due_seq = Some(due_seq);
+                false
+            },
+            None if self.last_due_seq.is_none() => {
+                // Nothing has ever been due yet: we're still filling the delay buffer with the
+                // call's first packet(s), not recovering from a loss. There's no prior audio to
+                // conceal, so just emit silence rather than handing the brand-new lookahead
+                // packet to the decoder as if it were FEC data for a frame that never existed.
+                true
+            },
+            None if self.enable_fec => match &self.held {
+                Some((due_seq, next_payload)) => {
+                    // The due frame was lost: pull it out of the *following* packet's FEC data.
+                    let len = decoder.decode(Some(next_payload.as_slice()), &mut out, true)?;
+                    out.truncate(len * 2);
+                    self.last_due_seq = Some(due_seq.wrapping_sub(1));
+                    true
+                },
+                None => {
+                    // No lookahead packet available yet either: fall back to PLC.
+                    let len = decoder.decode(None, &mut out, false)?;
+                    out.truncate(len * 2);
+                    self.last_due_seq = self.last_due_seq.map(|s| s.wrapping_add(1));
+                    true
+                },
+            },
+            None => {
+                // FEC disabled, or nothing to recover from: synthesise audio with Opus's PLC.
+                let len = decoder.decode(None, &mut out, false)?;
+                out.truncate(len * 2);
+                self.last_due_seq = self.last_due_seq.map(|s| s.wrapping_add(1));
+                true
+            },
+        };
+
+        Ok((out, concealed))
+    }
+}
+
+/// Owns one [`OpusDecoder`] and [`SsrcLossState`] per speaking SSRC, and is the actual
+/// integration point the receive path drives each tick to populate
+/// [`VoiceData::decoded_voice`]/[`VoiceData::concealed`].
+///
+/// [`VoiceData::decoded_voice`]: crate::events::context::data::VoiceData::decoded_voice
+/// [`VoiceData::concealed`]: crate::events::context::data::VoiceData::concealed
+pub struct DecodeMap {
+    enable_fec: bool,
+    per_ssrc: HashMap<u32, (OpusDecoder, SsrcLossState)>,
+}
+
+impl DecodeMap {
+    /// Creates a decode map whose FEC behaviour is taken from `config`.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            enable_fec: config.decode_fec,
+            per_ssrc: HashMap::new(),
+        }
+    }
+
+    /// Decodes (or conceals/recovers) this tick's audio for `ssrc`, creating its decoder and
+    /// loss-concealment state on first use.
+    pub fn decode_tick(
+        &mut self,
+        ssrc: u32,
+        seq: Option<u16>,
+        payload: Option<Vec<u8>>,
+    ) -> Result<(Vec<i16>, bool), OpusError> {
+        let enable_fec = self.enable_fec;
+        let (decoder, state) = match self.per_ssrc.entry(ssrc) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => {
+                let decoder = OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo)?;
+                e.insert((decoder, SsrcLossState::new(enable_fec)))
+            },
+        };
+
+        state.decode_tick(decoder, seq, payload)
+    }
+
+    /// Whether `ssrc` has decode/loss-concealment state already tracked, i.e. it has sent at
+    /// least one packet this call that [`Self::decode_tick`] hasn't yet fully clocked out.
+    pub fn is_active(&self, ssrc: u32) -> bool {
+        self.per_ssrc
+            .get(&ssrc)
+            .is_some_and(|(_, state)| state.held.is_some())
+    }
+
+    /// Drops decode state for an SSRC that has left the call.
+    pub fn remove(&mut self, ssrc: u32) {
+        self.per_ssrc.remove(&ssrc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(enable_fec: bool) -> SsrcLossState {
+        SsrcLossState::new(enable_fec)
+    }
+
+    #[test]
+    fn first_tick_has_nothing_to_decode_yet() {
+        let mut s = state(true);
+        let mut decoder =
+            OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo).unwrap();
+
+        // Nothing was held before the very first packet arrives, so this call only buffers it.
+        let (out, concealed) = s.decode_tick(&mut decoder, Some(1), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        assert!(concealed, "no packet was due for decode on the first tick");
+        assert!(
+            out.iter().all(|&s| s == 0),
+            "the first packet must not be handed to the decoder as FEC data for a frame that never existed"
+        );
+    }
+
+    #[test]
+    fn on_time_packet_is_decoded_without_concealment() {
+        let mut s = state(true);
+        let mut decoder =
+            OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo).unwrap();
+
+        s.decode_tick(&mut decoder, Some(1), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        let (_, concealed) = s.decode_tick(&mut decoder, Some(2), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        assert!(!concealed, "packet 1 was held and then decoded on-time");
+    }
+
+    #[test]
+    fn lost_packet_falls_back_to_plc_when_no_lookahead() {
+        let mut s = state(true);
+        let mut decoder =
+            OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo).unwrap();
+
+        s.decode_tick(&mut decoder, Some(1), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        // Packet 1 is decoded (genuine) here; packet 2 never arrives, and nothing has since.
+        s.decode_tick(&mut decoder, None, None).unwrap();
+        let (_, concealed) = s.decode_tick(&mut decoder, None, None).unwrap();
+        assert!(concealed);
+    }
+
+    #[test]
+    fn lost_packet_recovered_via_fec_from_next_packet() {
+        let mut s = state(true);
+        let mut decoder =
+            OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo).unwrap();
+
+        s.decode_tick(&mut decoder, Some(1), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        // Packet 1 is decoded (genuine) here; packet 2 is lost.
+        let (_, first_concealed) = s.decode_tick(&mut decoder, None, None).unwrap();
+        assert!(!first_concealed, "packet 1 should have decoded genuinely");
+        // Packet 3 (carrying FEC for packet 2) arrives now, one tick late.
+        let (_, concealed) = s
+            .decode_tick(&mut decoder, Some(3), Some(vec![0xF8, 0xFF, 0xFE]))
+            .unwrap();
+        assert!(concealed, "packet 2 should be recovered via FEC, which still counts as concealed");
+    }
+
+    #[test]
+    fn fec_disabled_always_uses_plc_on_loss() {
+        let mut s = state(false);
+        let mut decoder =
+            OpusDecoder::new(audiopus::SampleRate::Hz48000, audiopus::Channels::Stereo).unwrap();
+
+        s.decode_tick(&mut decoder, Some(1), Some(vec![0xF8, 0xFF, 0xFE])).unwrap();
+        s.decode_tick(&mut decoder, None, None).unwrap();
+        let (_, concealed) = s
+            .decode_tick(&mut decoder, Some(3), Some(vec![0xF8, 0xFF, 0xFE]))
+            .unwrap();
+        assert!(concealed, "FEC disabled, so loss must fall back to PLC rather than recovery");
+    }
+}
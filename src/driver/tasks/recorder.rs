@@ -0,0 +1,529 @@
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use audiopus::{coder::Encoder as OpusEncoder, Application, Channels, SampleRate};
+use hound::{SampleFormat as WavSampleFormat, WavSpec, WavWriter};
+use ogg::PacketWriter;
+
+use crate::events::context::data::VoiceTick;
+
+/// Number of interleaved stereo i16 samples in one 20ms tick at 48kHz.
+const TICK_LEN: usize = 960 * 2;
+
+/// Whether a [`Recorder`] keeps each speaker's audio in its own file, or mixes every speaker
+/// down to a single stereo file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordingMode {
+    /// Write one file per SSRC.
+    PerTrack,
+    /// Write a single mixed-down stereo file.
+    Mixdown,
+}
+
+/// Output codec used for every file a [`Recorder`] writes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordingFormat {
+    /// Uncompressed 16-bit PCM, written as a `.wav` file.
+    Wav,
+    /// Opus audio in an Ogg container, written as a `.opus` file.
+    Opus,
+}
+
+/// A limit on how much a [`Recorder`] will capture before automatically stopping itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RecordingLimit {
+    /// Stop once this much wall-clock call time has been recorded.
+    pub max_duration: Option<Duration>,
+    /// Stop once the audio data written to any single output file reaches this many bytes.
+    ///
+    /// The check runs after each tick has been written, so a file can overshoot this by up to
+    /// one tick; container headers and framing are not counted.
+    pub max_bytes: Option<u64>,
+}
+
+/// Multi-track recorder which subscribes to a call's [`VoiceTick`]s and writes synchronised
+/// audio to disk.
+///
+/// Each [`VoiceTick::speaking`] entry becomes its own track; SSRCs in [`VoiceTick::silent`] are
+/// zero-filled so every track stays aligned to the same 20ms clock, since silence must otherwise
+/// be inferred from `SpeakingUpdate`s. A track created for a speaker who joins partway through a
+/// recording is likewise backfilled with silence for every tick that already elapsed, so it still
+/// lines up with tracks that started at the beginning of the call. Output is written
+/// incrementally tick-by-tick through buffered writers rather than held for the whole call.
+pub struct Recorder {
+    dir: PathBuf,
+    mode: RecordingMode,
+    format: RecordingFormat,
+    limit: RecordingLimit,
+    tracks: HashMap<u32, TrackWriter>,
+    mixdown: Option<TrackWriter>,
+    started_at: Option<Instant>,
+    stopped: bool,
+    /// Number of ticks processed so far since [`Self::start`]. Used to zero-pad a late-joining
+    /// SSRC's track up to the current point in the call when it's first created, so every
+    /// per-track file stays aligned to the same 20ms clock regardless of when its speaker joined.
+    ticks_elapsed: u64,
+}
+
+impl Recorder {
+    /// Creates a recorder which will write its output files into `dir`, which must already
+    /// exist.
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        mode: RecordingMode,
+        format: RecordingFormat,
+        limit: RecordingLimit,
+    ) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+            format,
+            limit,
+            tracks: HashMap::new(),
+            mixdown: None,
+            started_at: None,
+            stopped: false,
+            ticks_elapsed: 0,
+        }
+    }
+
+    /// Begins recording. Subsequent calls to [`Self::process_tick`] will write audio to disk
+    /// until [`Self::stop`] is called, or a configured [`RecordingLimit`] is hit.
+    pub fn start(&mut self) {
+        self.started_at = Some(Instant::now());
+        self.stopped = false;
+        self.ticks_elapsed = 0;
+    }
+
+    /// Stops recording and flushes every open track to disk.
+    pub fn stop(&mut self) -> Result<(), RecorderError> {
+        self.stopped = true;
+
+        for track in self.tracks.values_mut() {
+            track.finish()?;
+        }
+
+        if let Some(track) = self.mixdown.as_mut() {
+            track.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Feeds one tick of call audio into the recorder, creating any new per-SSRC tracks as
+    /// needed and writing each track's encoded audio incrementally.
+    ///
+    /// Does nothing if the recorder isn't currently [`Self::start`]ed, or has already hit a
+    /// configured limit. Hitting [`RecordingLimit::max_duration`] or
+    /// [`RecordingLimit::max_bytes`] both cleanly [`Self::stop`] the recorder rather than
+    /// returning an error, so every output file is left correctly finalised.
+    pub fn process_tick(&mut self, tick: &VoiceTick) -> Result<(), RecorderError> {
+        let Some(started_at) = self.started_at else {
+            return Ok(());
+        };
+
+        if self.stopped {
+            return Ok(());
+        }
+
+        if let Some(max) = self.limit.max_duration {
+            if started_at.elapsed() >= max {
+                return self.stop();
+            }
+        }
+
+        match self.mode {
+            RecordingMode::PerTrack => self.process_per_track(tick)?,
+            RecordingMode::Mixdown => self.process_mixdown(tick)?,
+        }
+
+        self.ticks_elapsed += 1;
+
+        if let Some(max) = self.limit.max_bytes {
+            if self.any_track_over(max) {
+                return self.stop();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn any_track_over(&self, max_bytes: u64) -> bool {
+        self.tracks.values().any(|t| t.bytes_written() >= max_bytes)
+            || self
+                .mixdown
+                .as_ref()
+                .is_some_and(|t| t.bytes_written() >= max_bytes)
+    }
+
+    fn process_per_track(&mut self, tick: &VoiceTick) -> Result<(), RecorderError> {
+        let format = self.format;
+        let dir = self.dir.clone();
+        let ticks_elapsed = self.ticks_elapsed;
+
+        for ssrc in &tick.silent {
+            let path = track_path(&dir, *ssrc, format);
+            let track = get_or_create_track(&mut self.tracks, *ssrc, &path, format, ticks_elapsed)?;
+            track.write_silence()?;
+        }
+
+        for (ssrc, data) in &tick.speaking {
+            let path = track_path(&dir, *ssrc, format);
+            let track = get_or_create_track(&mut self.tracks, *ssrc, &path, format, ticks_elapsed)?;
+
+            match &data.decoded_voice {
+                Some(samples) => track.write_samples(samples)?,
+                None => track.write_silence()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_mixdown(&mut self, tick: &VoiceTick) -> Result<(), RecorderError> {
+        let path = self.dir.join(match self.format {
+            RecordingFormat::Wav => "mixdown.wav",
+            RecordingFormat::Opus => "mixdown.opus",
+        });
+
+        if self.mixdown.is_none() {
+            self.mixdown = Some(TrackWriter::create(&path, 2, self.format)?);
+        }
+        let track = self.mixdown.as_mut().expect("just inserted");
+
+        let clamped = mix_down(tick);
+        track.write_samples(&clamped)
+    }
+}
+
+/// Sums every speaking user's decoded audio for this tick into a single clamped stereo buffer.
+fn mix_down(tick: &VoiceTick) -> Vec<i16> {
+    let mut mixed = [0i32; TICK_LEN];
+    for data in tick.speaking.values() {
+        if let Some(samples) = &data.decoded_voice {
+            for (acc, &s) in mixed.iter_mut().zip(samples.iter()) {
+                *acc += s as i32;
+            }
+        }
+    }
+
+    mixed
+        .iter()
+        .map(|&s| s.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+        .collect()
+}
+
+fn track_path(dir: &Path, ssrc: u32, format: RecordingFormat) -> PathBuf {
+    match format {
+        RecordingFormat::Wav => dir.join(format!("track-{ssrc}.wav")),
+        RecordingFormat::Opus => dir.join(format!("track-{ssrc}.opus")),
+    }
+}
+
+/// Returns `ssrc`'s track, creating it if this is its first appearance.
+///
+/// A freshly-created track is zero-padded with `ticks_elapsed` ticks of silence before being
+/// handed back, so a speaker who joins partway through a recording still lines up with every
+/// other track at the same point in the call, rather than starting over at tick zero.
+fn get_or_create_track<'a>(
+    tracks: &'a mut HashMap<u32, TrackWriter>,
+    ssrc: u32,
+    path: &Path,
+    format: RecordingFormat,
+    ticks_elapsed: u64,
+) -> Result<&'a mut TrackWriter, RecorderError> {
+    match tracks.entry(ssrc) {
+        std::collections::hash_map::Entry::Occupied(e) => Ok(e.into_mut()),
+        std::collections::hash_map::Entry::Vacant(e) => {
+            let mut track = TrackWriter::create(path, 2, format)?;
+            for _ in 0..ticks_elapsed {
+                track.write_silence()?;
+            }
+            Ok(e.insert(track))
+        },
+    }
+}
+
+/// A single track being written incrementally to disk, in whichever format the owning
+/// [`Recorder`] was configured with.
+enum TrackWriter {
+    Wav {
+        writer: WavWriter<BufWriter<File>>,
+        bytes_written: u64,
+    },
+    Opus {
+        encoder: OpusEncoder,
+        writer: PacketWriter<BufWriter<File>>,
+        serial: u32,
+        granule_pos: u64,
+        bytes_written: u64,
+    },
+}
+
+impl TrackWriter {
+    fn create(path: &Path, channels: u16, format: RecordingFormat) -> Result<Self, RecorderError> {
+        match format {
+            RecordingFormat::Wav => {
+                let spec = WavSpec {
+                    channels,
+                    sample_rate: 48_000,
+                    bits_per_sample: 16,
+                    sample_format: WavSampleFormat::Int,
+                };
+                let writer = WavWriter::create(path, spec).map_err(RecorderError::Wav)?;
+                Ok(Self::Wav {
+                    writer,
+                    bytes_written: 0,
+                })
+            },
+            RecordingFormat::Opus => {
+                let file = File::create(path).map_err(RecorderError::Io)?;
+                let encoder = OpusEncoder::new(
+                    SampleRate::Hz48000,
+                    if channels == 1 {
+                        Channels::Mono
+                    } else {
+                        Channels::Stereo
+                    },
+                    Application::Audio,
+                )
+                .map_err(RecorderError::Encode)?;
+
+                let mut writer = PacketWriter::new(BufWriter::new(file));
+                let serial = rand_serial(path);
+                let mut bytes_written = 0u64;
+
+                bytes_written += write_opus_head(&mut writer, serial, channels as u8)?;
+                bytes_written += write_opus_tags(&mut writer, serial)?;
+
+                Ok(Self::Opus {
+                    encoder,
+                    writer,
+                    serial,
+                    granule_pos: 0,
+                    bytes_written,
+                })
+            },
+        }
+    }
+
+    fn bytes_written(&self) -> u64 {
+        match self {
+            Self::Wav { bytes_written, .. } | Self::Opus { bytes_written, .. } => *bytes_written,
+        }
+    }
+
+    fn write_samples(&mut self, samples: &[i16]) -> Result<(), RecorderError> {
+        match self {
+            Self::Wav {
+                writer,
+                bytes_written,
+            } => {
+                for &sample in samples {
+                    writer.write_sample(sample).map_err(RecorderError::Wav)?;
+                }
+                *bytes_written += (samples.len() * 2) as u64;
+                Ok(())
+            },
+            Self::Opus {
+                encoder,
+                writer,
+                serial,
+                granule_pos,
+                bytes_written,
+            } => {
+                let mut packet = vec![0u8; 4000];
+                let len = encoder.encode(samples, &mut packet).map_err(RecorderError::Encode)?;
+                packet.truncate(len);
+
+                *granule_pos += 960;
+                *bytes_written += packet.len() as u64;
+
+                writer
+                    .write_packet(packet, *serial, ogg::PacketWriteEndInfo::NormalPacket, *granule_pos)
+                    .map_err(RecorderError::Io)
+            },
+        }
+    }
+
+    fn write_silence(&mut self) -> Result<(), RecorderError> {
+        self.write_samples(&[0i16; TICK_LEN])
+    }
+
+    fn finish(&mut self) -> Result<(), RecorderError> {
+        match self {
+            Self::Wav { writer, .. } => {
+                // `WavWriter` finalises (and fixes up the RIFF header) on drop, but we need to
+                // report IO errors, so flush explicitly rather than waiting for `Drop`.
+                writer.flush().map_err(RecorderError::Wav)
+            },
+            Self::Opus {
+                writer,
+                serial,
+                granule_pos,
+                ..
+            } => writer
+                .write_packet(Vec::new(), *serial, ogg::PacketWriteEndInfo::EndStream, *granule_pos)
+                .map_err(RecorderError::Io),
+        }
+    }
+}
+
+/// Builds and writes the mandatory Ogg Opus identification header (RFC 7845 section 5.1),
+/// returning the number of bytes written.
+fn write_opus_head(
+    writer: &mut PacketWriter<BufWriter<File>>,
+    serial: u32,
+    channels: u8,
+) -> Result<u64, RecorderError> {
+    let mut packet = Vec::with_capacity(19);
+    packet.extend_from_slice(b"OpusHead");
+    packet.push(1); // version
+    packet.push(channels);
+    packet.extend_from_slice(&0u16.to_le_bytes()); // pre-skip
+    packet.extend_from_slice(&48_000u32.to_le_bytes()); // original input sample rate
+    packet.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    packet.push(0); // channel mapping family (0 = mono/stereo, no mapping table)
+
+    let len = packet.len() as u64;
+    writer
+        .write_packet(packet, serial, ogg::PacketWriteEndInfo::EndPage, 0)
+        .map_err(RecorderError::Io)?;
+    Ok(len)
+}
+
+/// Builds and writes the mandatory Ogg Opus comment header (RFC 7845 section 5.2), returning
+/// the number of bytes written.
+fn write_opus_tags(writer: &mut PacketWriter<BufWriter<File>>, serial: u32) -> Result<u64, RecorderError> {
+    let vendor = b"songbird";
+    let mut packet = Vec::with_capacity(16 + vendor.len());
+    packet.extend_from_slice(b"OpusTags");
+    packet.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    packet.extend_from_slice(vendor);
+    packet.extend_from_slice(&0u32.to_le_bytes()); // user comment list length
+
+    let len = packet.len() as u64;
+    writer
+        .write_packet(packet, serial, ogg::PacketWriteEndInfo::EndPage, 0)
+        .map_err(RecorderError::Io)?;
+    Ok(len)
+}
+
+/// Derives a stable-ish Ogg stream serial from a track's output path, since recorder output
+/// doesn't otherwise need real randomness.
+fn rand_serial(path: &Path) -> u32 {
+    path.to_string_lossy()
+        .bytes()
+        .fold(0x811c9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x01000193))
+}
+
+/// Errors encountered while recording a call to disk.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum RecorderError {
+    /// Failed to create or write to an Ogg output file.
+    Io(io::Error),
+    /// Failed to create or write to a WAV output file.
+    Wav(hound::Error),
+    /// Failed to encode audio for a track.
+    Encode(audiopus::Error),
+}
+
+impl Display for RecorderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("recorder error: ")?;
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Wav(e) => write!(f, "{e}"),
+            Self::Encode(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl StdError for RecorderError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Wav(e) => Some(e),
+            Self::Encode(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::context::data::VoiceData;
+
+    fn tick_with(samples: &[(u32, Option<Vec<i16>>)], silent: &[u32]) -> VoiceTick {
+        VoiceTick {
+            speaking: samples
+                .iter()
+                .map(|(ssrc, decoded)| {
+                    (
+                        *ssrc,
+                        VoiceData {
+                            packet: None,
+                            decoded_voice: decoded.clone(),
+                            concealed: false,
+                        },
+                    )
+                })
+                .collect(),
+            silent: silent.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn mix_down_sums_and_clamps() {
+        let mut loud = vec![i16::MAX; TICK_LEN];
+        loud[1] = i16::MAX;
+        let tick = tick_with(&[(1, Some(loud.clone())), (2, Some(loud))], &[]);
+
+        let mixed = mix_down(&tick);
+        assert_eq!(mixed.len(), TICK_LEN);
+        assert!(mixed.iter().all(|&s| s == i16::MAX), "overflowing mix must clamp to i16::MAX");
+    }
+
+    #[test]
+    fn mix_down_treats_missing_decode_as_silence() {
+        let tick = tick_with(&[(1, None)], &[2]);
+        let mixed = mix_down(&tick);
+        assert!(mixed.iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn opus_head_packet_matches_rfc7845_layout() {
+        let mut writer = PacketWriter::new(BufWriter::new(
+            tempfile_for_test("opus_head_packet_matches_rfc7845_layout"),
+        ));
+        let written = write_opus_head(&mut writer, 1, 2).unwrap();
+        // "OpusHead" (8) + version (1) + channels (1) + pre-skip (2) + rate (4) + gain (2) + map family (1)
+        assert_eq!(written, 19);
+    }
+
+    #[test]
+    fn late_joining_track_is_zero_padded_to_current_tick() {
+        let path = std::env::temp_dir().join("songbird-recorder-test-late-joiner.wav");
+        let mut tracks = HashMap::new();
+
+        let track = get_or_create_track(&mut tracks, 1, &path, RecordingFormat::Wav, 3).unwrap();
+        assert_eq!(
+            track.bytes_written(),
+            3 * TICK_LEN as u64 * 2,
+            "a track created partway through a recording must be backfilled with silence for every prior tick"
+        );
+    }
+
+    fn tempfile_for_test(name: &str) -> File {
+        let path = std::env::temp_dir().join(format!("songbird-recorder-test-{name}.opus"));
+        File::create(path).expect("can create temp file for test")
+    }
+}
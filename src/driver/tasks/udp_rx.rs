@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use super::loss_concealment::DecodeMap;
+use crate::{
+    driver::Config,
+    events::context::data::{RtpData, VoiceData, VoiceTick},
+};
+
+/// One user's RTP packet for the current network tick, as parsed off the wire.
+///
+/// `sequence`/`opus_payload` are split out from `packet` so this module doesn't need to know how
+/// to pull Opus frame bytes back out of a parsed [`RtpData`]; the UDP receive loop already has
+/// both in hand by the time it builds one of these.
+pub struct IncomingPacket {
+    pub packet: RtpData,
+    pub sequence: u16,
+    pub opus_payload: Vec<u8>,
+}
+
+/// Drives [`DecodeMap`] once per 20ms network tick, turning this tick's incoming RTP packets into
+/// the [`VoiceTick`] fired out to event handlers.
+///
+/// This is the integration point [`DecodeMap`] and [`SsrcLossState`] exist for: without it, every
+/// call site that wants decoded, loss-concealed audio would need to reimplement the per-SSRC
+/// decode/PLC/FEC bookkeeping itself.
+///
+/// [`SsrcLossState`]: super::loss_concealment::SsrcLossState
+pub struct UdpRxState {
+    decode_map: DecodeMap,
+    /// Each SSRC's most recent [`RtpData`], held back one tick alongside its payload in
+    /// [`DecodeMap`] so it is reported with the audio decoded from it.
+    held_packets: HashMap<u32, RtpData>,
+}
+
+impl UdpRxState {
+    /// Creates fresh receive-path state for a call, taking FEC behaviour from `config`.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            decode_map: DecodeMap::new(config),
+            held_packets: HashMap::new(),
+        }
+    }
+
+    /// Forgets decode state for an SSRC that has left the call.
+    pub fn remove_ssrc(&mut self, ssrc: u32) {
+        self.decode_map.remove(ssrc);
+        self.held_packets.remove(&ssrc);
+    }
+
+    /// Processes one network tick's worth of packets into a [`VoiceTick`].
+    ///
+    /// An SSRC is considered speaking this tick (and so appears in [`VoiceTick::speaking`]) if it
+    /// sent a packet this tick, or if it already has in-flight decode state from a previous tick
+    /// that still needs to be clocked out (e.g. a held packet awaiting possible FEC recovery).
+    /// Any other known SSRC is reported as silent, without ever touching the decoder.
+    pub fn process_tick(
+        &mut self,
+        mut packets: HashMap<u32, IncomingPacket>,
+        known_ssrcs: impl IntoIterator<Item = u32>,
+    ) -> Result<VoiceTick, audiopus::Error> {
+        let mut speaking = HashMap::new();
+        let mut silent = std::collections::HashSet::new();
+
+        for ssrc in known_ssrcs {
+            let incoming = packets.remove(&ssrc);
+
+            if incoming.is_none() && !self.decode_map.is_active(ssrc) {
+                silent.insert(ssrc);
+                continue;
+            }
+
+            let (packet, seq, payload) = match incoming {
+                Some(p) => (Some(p.packet), Some(p.sequence), Some(p.opus_payload)),
+                None => (None, None, None),
+            };
+
+            let (decoded_voice, concealed) = self.decode_map.decode_tick(ssrc, seq, payload)?;
+
+            // The audio just decoded belongs to the packet held back last tick, not this one.
+            let packet = match packet {
+                Some(p) => self.held_packets.insert(ssrc, p),
+                None => self.held_packets.remove(&ssrc),
+            };
+
+            speaking.insert(
+                ssrc,
+                VoiceData {
+                    packet,
+                    decoded_voice: Some(decoded_voice),
+                    concealed,
+                },
+            );
+        }
+
+        Ok(VoiceTick { speaking, silent })
+    }
+}
@@ -25,8 +25,10 @@ pub struct VoiceTick {
 pub struct VoiceData {
     /// RTP packet clocked out for this tick.
     ///
-    /// If `None`, then the packet was lost, and [`Self::decoded_voice`] may include
-    /// around one codec delay's worth of audio.
+    /// If `None`, then the packet was lost. Depending on [`Config::decode_fec`], [`Self::decoded_voice`]
+    /// may still hold a recovered or concealed frame for this tick rather than silence.
+    ///
+    /// [`Config::decode_fec`]: crate::Config::decode_fec
     pub packet: Option<RtpData>,
     /// PCM audio obtained from a user.
     ///
@@ -35,4 +37,10 @@ pub struct VoiceData {
     ///
     /// This value will be `None` if Songbird is not configured to decode audio.
     pub decoded_voice: Option<Vec<i16>>,
+    /// Whether [`Self::decoded_voice`] (if present) was synthesised by the decoder rather than
+    /// decoded from a genuine packet for this tick.
+    ///
+    /// This is `true` for both in-band FEC recovery of a previous lost frame and for plain
+    /// packet-loss concealment (PLC), and `false` whenever [`Self::packet`] was decoded directly.
+    pub concealed: bool,
 }
@@ -0,0 +1,316 @@
+use std::{
+    io::{Read, Seek, SeekFrom},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use cpal::{
+    traits::{DeviceTrait, StreamTrait},
+    Device, Sample, SampleFormat, StreamConfig,
+};
+use symphonia_core::io::MediaSource;
+
+use super::{error::Error as InputError, AudioStream, AudioStreamError, Compose, Input};
+
+/// Target sample rate and channel count songbird expects from any [`Input`].
+const TARGET_RATE: u32 = 48_000;
+const TARGET_CHANNELS: usize = 2;
+
+/// How long [`CpalReader::read`] will wait for fresh samples before checking whether the
+/// capture thread has failed and re-polling.
+const READ_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// An [`Input`]/[`Compose`] which sources live audio from a local microphone (or any other
+/// cpal input [`Device`]), resampling it to the 48kHz stereo PCM songbird expects.
+///
+/// Unlike most other sources, this never finishes on its own: reads block until audio is
+/// available, and only end once the underlying cpal stream is torn down (by dropping the
+/// returned [`AudioStream`], or on an unrecoverable device error).
+///
+/// [`Input`]: super::Input
+/// [`AudioStream`]: super::AudioStream
+pub struct CpalSource {
+    device: Device,
+}
+
+impl CpalSource {
+    /// Creates a new source which will read from `device` when made live.
+    pub fn new(device: Device) -> Self {
+        Self { device }
+    }
+
+    /// Creates a new source using cpal's default input device, if one is available.
+    pub fn from_default_device() -> Result<Self, AudioStreamError> {
+        cpal::default_host()
+            .default_input_device()
+            .map(Self::new)
+            .ok_or(AudioStreamError::Unsupported)
+    }
+}
+
+impl From<CpalSource> for Input {
+    fn from(val: CpalSource) -> Self {
+        Input::Lazy(Box::new(val))
+    }
+}
+
+#[async_trait::async_trait]
+impl Compose for CpalSource {
+    fn create(&mut self) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        let config = self
+            .device
+            .default_input_config()
+            .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+        let reader = CpalReader::open(self.device.clone(), config.config(), config.sample_format())
+            .map_err(|e| AudioStreamError::Fail(Box::new(e)))?;
+
+        Ok(AudioStream {
+            input: Box::new(reader),
+            hint: None,
+        })
+    }
+
+    async fn create_async(
+        &mut self,
+    ) -> Result<AudioStream<Box<dyn MediaSource>>, AudioStreamError> {
+        self.create()
+    }
+
+    fn should_create_async(&self) -> bool {
+        false
+    }
+}
+
+/// A [`MediaSource`] fed by a running cpal input stream, resampling its output to 48kHz stereo
+/// i16 PCM as samples arrive.
+///
+/// cpal's `Stream` is `!Send` on most platforms, so it can't be stored directly in a reader that
+/// may be moved across threads (as any [`MediaSource`] boxed into an [`AudioStream`] can be).
+/// Instead, the stream is built and played on a dedicated capture thread which owns it for its
+/// entire lifetime: [`Self::read`] only ever touches the channel and atomics below, and dropping
+/// this reader signals that thread to tear the stream down and exit.
+///
+/// The stream's error callback never panics: failures are latched in [`Self::failed`] and
+/// surfaced on the next [`Read::read`] as an `io::Error` instead, so a disconnected or failed
+/// device fails the track cleanly rather than taking down the driver.
+struct CpalReader {
+    rx: Receiver<i16>,
+    failed: Arc<AtomicBool>,
+    shutdown: Option<SyncSender<()>>,
+    capture_thread: Option<JoinHandle<()>>,
+    buf: Vec<u8>,
+}
+
+impl CpalReader {
+    fn open(
+        device: Device,
+        config: StreamConfig,
+        sample_format: SampleFormat,
+    ) -> Result<Self, InputError> {
+        let (tx, rx) = sync_channel(TARGET_RATE as usize * TARGET_CHANNELS);
+        let failed = Arc::new(AtomicBool::new(false));
+        let (shutdown_tx, shutdown_rx) = sync_channel::<()>(0);
+        let (ready_tx, ready_rx) = sync_channel::<Result<(), cpal::BuildStreamError>>(1);
+
+        let failed_thread = failed.clone();
+        let capture_thread = std::thread::spawn(move || {
+            match build_and_play_stream(&device, &config, sample_format, tx, failed_thread) {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    // Block here, keeping `stream` alive on its owning thread, until the
+                    // reader is dropped and closes `shutdown_tx`.
+                    let _ = shutdown_rx.recv();
+                    drop(stream);
+                },
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e));
+                },
+            }
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self {
+                rx,
+                failed,
+                shutdown: Some(shutdown_tx),
+                capture_thread: Some(capture_thread),
+                buf: Vec::new(),
+            }),
+            Ok(Err(e)) => {
+                let _ = capture_thread.join();
+                Err(InputError::Cpal(e))
+            },
+            Err(_) => {
+                let _ = capture_thread.join();
+                Err(InputError::Cpal(cpal::BuildStreamError::DeviceNotAvailable))
+            },
+        }
+    }
+}
+
+impl Drop for CpalReader {
+    fn drop(&mut self) {
+        // Dropping the sender closes the channel, waking the capture thread's `recv()` so it
+        // can drop the (thread-local, `!Send`) `Stream` and exit.
+        self.shutdown.take();
+
+        if let Some(thread) = self.capture_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn build_and_play_stream(
+    device: &Device,
+    config: &StreamConfig,
+    sample_format: SampleFormat,
+    tx: SyncSender<i16>,
+    failed: Arc<AtomicBool>,
+) -> Result<cpal::Stream, cpal::BuildStreamError> {
+    let in_channels = config.channels as usize;
+    let in_rate = config.sample_rate.0;
+
+    let err_cb = move |err: cpal::StreamError| {
+        tracing::error!("cpal input stream error: {err}");
+        failed.store(true, Ordering::SeqCst);
+    };
+
+    let stream = match sample_format {
+        SampleFormat::F32 => device.build_input_stream(
+            config,
+            move |data: &[f32], _: &_| resample_and_send(data, in_channels, in_rate, &tx),
+            err_cb,
+            None,
+        )?,
+        SampleFormat::I16 => device.build_input_stream(
+            config,
+            move |data: &[i16], _: &_| {
+                let floats: Vec<f32> = data.iter().map(|s| s.to_float_sample()).collect();
+                resample_and_send(&floats, in_channels, in_rate, &tx)
+            },
+            err_cb,
+            None,
+        )?,
+        _ => return Err(cpal::BuildStreamError::StreamConfigNotSupported),
+    };
+
+    stream
+        .play()
+        .map_err(|_| cpal::BuildStreamError::DeviceNotAvailable)?;
+
+    Ok(stream)
+}
+
+/// Converts one callback's worth of interleaved input samples to stereo 48kHz audio (taking the
+/// first two channels, duplicating channel 0 for mono input) via simple nearest-neighbour
+/// resampling, and pushes it onto the reader's channel.
+///
+/// This favours simplicity and boundedness over audio fidelity; a production deployment wanting
+/// higher quality can swap this for a proper windowed resampler without touching the rest of
+/// [`CpalSource`].
+fn resample_and_send(data: &[f32], in_channels: usize, in_rate: u32, tx: &SyncSender<i16>) {
+    if in_channels == 0 || in_rate == 0 {
+        return;
+    }
+
+    let frames: Vec<(f32, f32)> = data
+        .chunks(in_channels)
+        .map(|frame| {
+            let l = frame[0];
+            let r = *frame.get(1).unwrap_or(&l);
+            (l, r)
+        })
+        .collect();
+
+    let out_len = (frames.len() as u64 * TARGET_RATE as u64 / in_rate as u64) as usize;
+    for i in 0..out_len {
+        let src_idx = (i as u64 * in_rate as u64 / TARGET_RATE as u64) as usize;
+        let Some(&(l, r)) = frames.get(src_idx) else {
+            break;
+        };
+        let _ = tx.try_send((l * i16::MAX as f32) as i16);
+        let _ = tx.try_send((r * i16::MAX as f32) as i16);
+    }
+}
+
+impl Read for CpalReader {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.buf.len() < out.len() {
+            if self.failed.load(Ordering::SeqCst) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "cpal input stream failed",
+                ));
+            }
+
+            match self.rx.recv_timeout(READ_POLL_INTERVAL) {
+                Ok(sample) => self.buf.extend_from_slice(&sample.to_ne_bytes()),
+                // The mic just hasn't produced a callback's worth of audio yet: keep blocking
+                // rather than returning `Ok(0)`, which `MediaSource` readers treat as EOF.
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        "cpal input stream disconnected",
+                    ));
+                },
+            }
+        }
+
+        let n = out.len().min(self.buf.len());
+        out[..n].copy_from_slice(&self.buf[..n]);
+        self.buf.drain(..n);
+        Ok(n)
+    }
+}
+
+impl Seek for CpalReader {
+    fn seek(&mut self, _: SeekFrom) -> std::io::Result<u64> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "cpal input cannot seek",
+        ))
+    }
+}
+
+impl MediaSource for CpalReader {
+    fn is_seekable(&self) -> bool {
+        false
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resample_upsamples_mono_to_stereo_48k() {
+        let (tx, rx) = sync_channel(64);
+        // 4 mono frames at 24kHz should become 16 stereo samples (8 frames) at 48kHz.
+        resample_and_send(&[0.5, -0.5, 0.25, -0.25], 1, 24_000, &tx);
+        drop(tx);
+
+        let received: Vec<i16> = rx.try_iter().collect();
+        assert_eq!(received.len(), 16);
+    }
+
+    #[test]
+    fn resample_ignores_zero_rate_or_channels() {
+        let (tx, rx) = sync_channel(8);
+        resample_and_send(&[0.1, 0.2], 0, 48_000, &tx);
+        resample_and_send(&[0.1, 0.2], 2, 0, &tx);
+        drop(tx);
+
+        assert_eq!(rx.try_iter().count(), 0);
+    }
+}
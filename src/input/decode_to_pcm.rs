@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use symphonia_core::{
+    audio::SampleBuffer,
+    codecs::DecoderOptions,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use super::{Compose, Input, MakePlayableError};
+
+/// Target sample rate and channel count [`decode_to_pcm`] always produces.
+const TARGET_RATE: u32 = 48_000;
+const TARGET_CHANNELS: u16 = 2;
+
+/// The fully-decoded result of [`decode_to_pcm`]: one interleaved buffer of 16-bit PCM samples,
+/// resampled to 48kHz stereo regardless of the source's native format.
+pub struct DecodedPcm {
+    /// Interleaved 16-bit stereo PCM samples (`L, R, L, R, ...`) at 48kHz.
+    pub samples: Vec<i16>,
+    /// Number of channels [`Self::samples`] is interleaved as. Always 2 (stereo).
+    pub channels: u16,
+    /// Sample rate of [`Self::samples`], in Hz. Always 48,000.
+    pub sample_rate: u32,
+    /// Total playback duration of the decoded audio.
+    pub duration: Duration,
+}
+
+/// Eagerly decodes an entire [`Input`] into memory, mirroring the `decode_audio_data` pattern
+/// from web-audio engines: instead of streaming frame-by-frame through the live driver, every
+/// sample is produced up front, resampled to 48kHz stereo, so callers can compute waveforms, run
+/// loudness/peak analysis, or pre-buffer short sound effects.
+///
+/// Only a still-[`Input::Lazy`] input is supported: once an input has been made live/parsed it
+/// may already have been partially consumed by the driver, and songbird doesn't expose a way to
+/// rewind it from outside `input::`. Call this before handing the input to a track/driver,
+/// rather than after.
+///
+/// The actual decode runs on a blocking worker thread, so this is safe to `.await` from an
+/// async context without stalling it. Failures are surfaced through the same
+/// [`MakePlayableError`] variants used elsewhere in songbird.
+pub async fn decode_to_pcm(source: Input) -> Result<DecodedPcm, MakePlayableError> {
+    let Input::Lazy(compose) = source else {
+        return Err(MakePlayableError::Parse(symphonia_core::errors::Error::Unsupported(
+            "decode_to_pcm only supports inputs that are still Lazy (not yet made playable)",
+        )));
+    };
+
+    decode_compose_to_pcm(compose).await
+}
+
+/// As [`decode_to_pcm`], but for a bare [`Compose`] that hasn't been wrapped in an [`Input`] yet.
+pub async fn decode_compose_to_pcm(mut source: impl Compose + 'static) -> Result<DecodedPcm, MakePlayableError> {
+    match tokio::task::spawn_blocking(move || decode_to_pcm_blocking(&mut source)).await {
+        Ok(result) => result,
+        Err(_) => Err(MakePlayableError::Panicked),
+    }
+}
+
+fn decode_to_pcm_blocking(source: &mut impl Compose) -> Result<DecodedPcm, MakePlayableError> {
+    let stream = source.create()?;
+    let mss = MediaSourceStream::new(stream.input, Default::default());
+
+    let probed = symphonia::default::get_probe().format(
+        stream.hint.as_ref().unwrap_or(&Hint::new()),
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != symphonia_core::codecs::CODEC_TYPE_NULL)
+        .ok_or(MakePlayableError::Parse(symphonia_core::errors::Error::Unsupported(
+            "no decodable track",
+        )))?
+        .clone();
+
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let source_rate = track.codec_params.sample_rate.unwrap_or(TARGET_RATE);
+    let source_channels = track
+        .codec_params
+        .channels
+        .map(|c| c.count() as u16)
+        .unwrap_or(TARGET_CHANNELS);
+
+    let mut raw_samples = Vec::new();
+    let mut sample_buf: Option<SampleBuffer<i16>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(symphonia_core::errors::Error::IoError(_)) => break,
+            Err(e) => return Err(e.into()),
+        };
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(symphonia_core::errors::Error::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        let buf = sample_buf.get_or_insert_with(|| {
+            SampleBuffer::new(decoded.capacity() as u64, *decoded.spec())
+        });
+        buf.copy_interleaved_ref(decoded);
+        raw_samples.extend_from_slice(buf.samples());
+    }
+
+    let stereo = remix_to_stereo(&raw_samples, source_channels);
+    let samples = resample_stereo(&stereo, source_rate, TARGET_RATE);
+
+    let total_frames = samples.len() / TARGET_CHANNELS as usize;
+    let duration = Duration::from_secs_f64(total_frames as f64 / TARGET_RATE as f64);
+
+    Ok(DecodedPcm {
+        samples,
+        channels: TARGET_CHANNELS,
+        sample_rate: TARGET_RATE,
+        duration,
+    })
+}
+
+/// Downmixes/upmixes interleaved `in_channels`-channel audio to stereo frames, by taking the
+/// first two channels (duplicating channel 0 for mono sources).
+fn remix_to_stereo(samples: &[i16], in_channels: u16) -> Vec<(i16, i16)> {
+    let in_channels = in_channels.max(1) as usize;
+    samples
+        .chunks(in_channels)
+        .filter(|frame| frame.len() == in_channels)
+        .map(|frame| {
+            let l = frame[0];
+            let r = *frame.get(1).unwrap_or(&l);
+            (l, r)
+        })
+        .collect()
+}
+
+/// Resamples stereo frames from `in_rate` to `out_rate` via nearest-neighbour selection, and
+/// interleaves the result.
+///
+/// This favours simplicity and boundedness over audio fidelity, matching the same tradeoff made
+/// by the cpal input source's resampler.
+fn resample_stereo(frames: &[(i16, i16)], in_rate: u32, out_rate: u32) -> Vec<i16> {
+    if frames.is_empty() {
+        return Vec::new();
+    }
+
+    if in_rate == out_rate {
+        return frames.iter().flat_map(|&(l, r)| [l, r]).collect();
+    }
+
+    let out_len = (frames.len() as u64 * out_rate as u64 / in_rate.max(1) as u64) as usize;
+    let mut out = Vec::with_capacity(out_len * 2);
+
+    for i in 0..out_len {
+        let src_idx = (i as u64 * in_rate as u64 / out_rate as u64) as usize;
+        let &(l, r) = frames.get(src_idx).unwrap_or(frames.last().expect("checked non-empty above"));
+        out.push(l);
+        out.push(r);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remix_mono_duplicates_to_stereo() {
+        let stereo = remix_to_stereo(&[1, 2, 3], 1);
+        assert_eq!(stereo, vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn remix_keeps_first_two_of_wider_layouts() {
+        // 4-channel input: takes channels 0 and 1, drops 2 and 3.
+        let stereo = remix_to_stereo(&[1, 2, 3, 4, 5, 6, 7, 8], 4);
+        assert_eq!(stereo, vec![(1, 2), (5, 6)]);
+    }
+
+    #[test]
+    fn resample_is_identity_at_matching_rate() {
+        let frames = vec![(1, -1), (2, -2)];
+        assert_eq!(resample_stereo(&frames, 48_000, 48_000), vec![1, -1, 2, -2]);
+    }
+
+    #[test]
+    fn resample_upsamples_to_target_length() {
+        let frames = vec![(1, -1), (2, -2)];
+        let out = resample_stereo(&frames, 24_000, 48_000);
+        assert_eq!(out.len(), 8, "2 frames at half rate should become ~4 frames at target rate");
+    }
+
+    #[test]
+    fn resample_handles_empty_input() {
+        assert!(resample_stereo(&[], 24_000, 48_000).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use std::{error::Error, fmt::Display, time::Duration};
+use cpal::BuildStreamError;
 use symphonia_core::errors::Error as SymphError;
 
 /// Errors encountered when creating an [`AudioStream`] or requesting metadata
@@ -8,6 +9,8 @@ use symphonia_core::errors::Error as SymphError;
 /// [`Compose`]: super::Compose
 #[non_exhaustive]
 pub enum Error {
+    /// An error occurred while opening a cpal input stream (e.g., from a local microphone).
+    Cpal(BuildStreamError),
     /// An error occurred while opening a new DCA source.
     Dca(DcaError),
     /// An error occurred while reading, or opening a file.
@@ -41,6 +44,12 @@ pub enum Error {
     YouTubeDlUrl(Value),
 }
 
+impl From<BuildStreamError> for Error {
+    fn from(e: BuildStreamError) -> Self {
+        Error::Cpal(e)
+    }
+}
+
 impl From<CatcherError> for Error {
     fn from(e: CatcherError) -> Self {
         Error::Streamcatcher(e)
@@ -68,6 +77,7 @@ impl From<OpusError> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::Cpal(e) => write!(f, "opening cpal input stream failed: {e}"),
             Error::Dca(_) => write!(f, "opening file DCA failed"),
             Error::Io(e) => e.fmt(f),
             Error::Json {
@@ -89,6 +99,7 @@ impl fmt::Display for Error {
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
+            Error::Cpal(e) => Some(e),
             Error::Dca(e) => Some(e),
             Error::Io(e) => e.source(),
             Error::Json {